@@ -0,0 +1,17 @@
+//! Regenerates `assets/default_themes.themedump` from the source
+//! `.tmTheme` files in `assets/default_themes/`.
+//!
+//! Run with `cargo run --example gen_default_themes` after editing one of
+//! those files (or adding a new one) and commit the resulting
+//! `assets/default_themes.themedump` alongside your change - `load_defaults`
+//! only ever reads the dump, it never parses the source themes itself.
+extern crate syntect;
+
+use syntect::highlighting::ThemeSet;
+
+fn main() {
+    let themes = ThemeSet::load_from_folder("assets/default_themes")
+        .expect("assets/default_themes should contain only valid .tmTheme files");
+    themes.dump_to_file("assets/default_themes.themedump")
+        .expect("failed to write assets/default_themes.themedump");
+}
@@ -0,0 +1,34 @@
+//! Caches large, expensive-to-parse data structures (like a `ThemeSet`) to
+//! a compact binary format so they can be loaded back almost instantly,
+//! instead of re-parsing the original assets on every startup.
+use std::io::{self, Read, Write, BufReader, BufWriter};
+use std::fs::File;
+use std::path::Path;
+use rustc_serialize::{Encodable, Decodable};
+use bincode::rustc_serialize::{encode_into, decode_from};
+use bincode::SizeLimit;
+
+/// Dumps an encodable structure to a file at `path`, overwriting it if it
+/// already exists.
+pub fn dump_to_file<T: Encodable, P: AsRef<Path>>(o: &T, path: P) -> io::Result<()> {
+    let mut f = BufWriter::new(try!(File::create(path)));
+    dump_to_reader(o, &mut f)
+}
+
+/// Dumps an encodable structure to anything implementing `Write`.
+pub fn dump_to_reader<T: Encodable, W: Write>(o: &T, mut writer: W) -> io::Result<()> {
+    encode_into(o, &mut writer, SizeLimit::Infinite)
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("{}", e)))
+}
+
+/// Loads a structure previously written with `dump_to_file`.
+pub fn from_dump_file<T: Decodable, P: AsRef<Path>>(path: P) -> io::Result<T> {
+    let f = BufReader::new(try!(File::open(path)));
+    from_reader(f)
+}
+
+/// Loads a structure previously written with `dump_to_reader`.
+pub fn from_reader<T: Decodable, R: Read>(mut reader: R) -> io::Result<T> {
+    decode_from(&mut reader, SizeLimit::Infinite)
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("{}", e)))
+}
@@ -0,0 +1,13 @@
+//! Types for representing and loading Sublime Text/TextMate `.tmTheme`
+//! color schemes, used to style a parsed syntax tree.
+mod theme;
+mod theme_set;
+mod style;
+mod selector;
+mod settings;
+
+pub use self::theme::{Theme, ThemeAppearance, ThemeSettings, ThemeItem, ParseThemeError};
+pub use self::theme_set::{ThemeSet, ThemeSetError};
+pub use self::style::{Color, FontStyle, StyleModifier};
+pub use self::selector::ScopeSelectors;
+pub use self::settings::{Settings, SettingsError};
@@ -0,0 +1,23 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// A scope selector as found in a `ThemeItem`'s `scope` key, e.g.
+/// `"comment, string.quoted"`. Kept as the raw selector text rather than
+/// parsed into scope paths, since matching selectors against scope stacks
+/// is handled by the parsing crate; this type just needs to round-trip
+/// through a theme file and compare for equality when merging themes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct ScopeSelectors(pub String);
+
+impl FromStr for ScopeSelectors {
+    type Err = ();
+    fn from_str(s: &str) -> Result<ScopeSelectors, ()> {
+        Ok(ScopeSelectors(s.to_owned()))
+    }
+}
+
+impl fmt::Display for ScopeSelectors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,39 @@
+use std::io::{Read, Write};
+use std::fmt;
+use std::error::Error;
+use plist::Plist;
+
+/// A loaded but not yet interpreted plist, used as the intermediate
+/// representation for parsing `.tmTheme` files (and anything else that
+/// is plist-shaped, like syntax definitions).
+pub use plist::Plist as Settings;
+
+#[derive(Debug)]
+pub enum SettingsError {
+    Plist(::plist::Error),
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SettingsError::Plist(ref e) => write!(f, "Error parsing plist: {}", e),
+        }
+    }
+}
+
+impl Error for SettingsError {
+    fn description(&self) -> &str {
+        "Error parsing settings"
+    }
+}
+
+/// Reads a plist (XML property list) from anything implementing `Read`.
+pub fn read_plist<R: Read>(reader: R) -> Result<Settings, SettingsError> {
+    Plist::read(reader).map_err(SettingsError::Plist)
+}
+
+/// Writes a plist (XML property list) to anything implementing `Write`,
+/// the inverse of `read_plist`.
+pub fn write_plist<W: Write>(settings: &Settings, writer: W) -> Result<(), SettingsError> {
+    settings.clone().write(writer).map_err(SettingsError::Plist)
+}
@@ -0,0 +1,40 @@
+/// An RGBA color, with components in the range 0-255.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    pub const WHITE: Color = Color { r: 0xFF, g: 0xFF, b: 0xFF, a: 0xFF };
+    pub const BLACK: Color = Color { r: 0x00, g: 0x00, b: 0x00, a: 0xFF };
+
+    /// The perceptual brightness of this color on a 0.0 (black) - 1.0
+    /// (white) scale, ignoring alpha. Used to guess whether a theme is
+    /// dark or light from its background color.
+    pub fn relative_luminance(&self) -> f32 {
+        0.2126 * (self.r as f32 / 255.0) + 0.7152 * (self.g as f32 / 255.0) +
+        0.0722 * (self.b as f32 / 255.0)
+    }
+}
+
+bitflags! {
+    #[derive(Default, RustcEncodable, RustcDecodable)]
+    pub flags FontStyle: u8 {
+        const BOLD = 1,
+        const UNDERLINE = 2,
+        const ITALIC = 4,
+    }
+}
+
+/// A set of style changes that can be applied to text, used both as the
+/// per-scope overrides in a `Theme` and as the final computed style of a
+/// highlighted token.
+#[derive(Debug, Clone, Copy, Default, PartialEq, RustcEncodable, RustcDecodable)]
+pub struct StyleModifier {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub font_style: Option<FontStyle>,
+}
@@ -0,0 +1,251 @@
+use super::settings::*;
+use super::style::{Color, StyleModifier};
+use super::selector::ScopeSelectors;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::io::Write;
+
+/// A parsed `.tmTheme` file, ready to drive a `Highlighter`.
+#[derive(Debug, Clone, Default, PartialEq, RustcEncodable, RustcDecodable)]
+pub struct Theme {
+    pub name: Option<String>,
+    pub author: Option<String>,
+    /// Whether this theme is meant to be used against a dark or light
+    /// background. `None` if the theme didn't say and the background
+    /// color wasn't dark/light enough to guess confidently.
+    pub appearance: Option<ThemeAppearance>,
+    pub settings: ThemeSettings,
+    pub scopes: Vec<ThemeItem>,
+}
+
+/// Whether a theme is designed for a dark or light background, so a host
+/// application can offer a "dark theme"/"light theme" picker or follow OS
+/// appearance automatically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum ThemeAppearance {
+    Light,
+    Dark,
+}
+
+/// The global colors of a theme - caret, selection, gutter, and so on -
+/// as opposed to the per-scope overrides in `scopes`.
+#[derive(Debug, Clone, Default, PartialEq, RustcEncodable, RustcDecodable)]
+pub struct ThemeSettings {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub caret: Option<Color>,
+    pub line_highlight: Option<Color>,
+    pub selection: Option<Color>,
+    pub selection_foreground: Option<Color>,
+    pub guide: Option<Color>,
+    pub gutter: Option<Color>,
+    pub gutter_foreground: Option<Color>,
+}
+
+/// A single `<dict>` entry in a theme's `settings` array: a scope
+/// selector and the style to apply where it matches.
+#[derive(Debug, Clone, Default, PartialEq, RustcEncodable, RustcDecodable)]
+pub struct ThemeItem {
+    pub scope: ScopeSelectors,
+    pub style: StyleModifier,
+}
+
+#[derive(Debug)]
+pub enum ParseThemeError {
+    ColorSchemeSettingsNotObject,
+    ScopeSettingsIsNotObject,
+    IncorrectColor,
+    UndefinedScopeSettings,
+}
+
+impl fmt::Display for ParseThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for ParseThemeError {
+    fn description(&self) -> &str {
+        "Error parsing theme"
+    }
+}
+
+fn dict(settings: &Settings) -> Result<&BTreeMap<String, Settings>, ParseThemeError> {
+    match *settings {
+        Settings::Dictionary(ref d) => Ok(d),
+        _ => Err(ParseThemeError::ColorSchemeSettingsNotObject),
+    }
+}
+
+fn color(settings: &Settings) -> Option<Color> {
+    settings.as_string().and_then(|s| parse_hex_color(s))
+}
+
+fn parse_hex_color(s: &str) -> Option<Color> {
+    let s = s.trim_left_matches('#');
+    let digits: Vec<u8> = (0..s.len() / 2)
+        .filter_map(|i| u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).ok())
+        .collect();
+    match digits.len() {
+        3 => Some(Color { r: digits[0], g: digits[1], b: digits[2], a: 0xFF }),
+        4 => Some(Color { r: digits[0], g: digits[1], b: digits[2], a: digits[3] }),
+        _ => None,
+    }
+}
+
+impl Theme {
+    /// Parses a loaded `.tmTheme` plist into a `Theme`.
+    pub fn parse_settings(settings: Settings) -> Result<Theme, ParseThemeError> {
+        let mut obj = try!(dict(&settings)).clone();
+
+        let name = obj.remove("name").and_then(|v| v.as_string().map(|s| s.to_owned()));
+        let author = obj.remove("author").and_then(|v| v.as_string().map(|s| s.to_owned()));
+        let appearance = obj.remove("appearance")
+            .and_then(|v| v.as_string().map(|s| s.to_owned()))
+            .and_then(|s| match s.to_lowercase().as_str() {
+                "dark" => Some(ThemeAppearance::Dark),
+                "light" => Some(ThemeAppearance::Light),
+                _ => None,
+            });
+
+        let mut theme_settings = ThemeSettings::default();
+        let mut scopes = Vec::new();
+        if let Some(Settings::Array(items)) = obj.remove("settings") {
+            for item in items {
+                let item_dict = try!(dict(&item));
+                match item_dict.get("scope") {
+                    None => {
+                        if let Some(global) = item_dict.get("settings") {
+                            theme_settings = try!(parse_theme_settings(global));
+                        }
+                    }
+                    Some(scope) => {
+                        let scope_str = scope.as_string().unwrap_or("").to_owned();
+                        let style_settings = item_dict.get("settings")
+                            .ok_or(ParseThemeError::UndefinedScopeSettings)?;
+                        scopes.push(ThemeItem {
+                            scope: ScopeSelectors(scope_str),
+                            style: try!(parse_style_modifier(style_settings)),
+                        });
+                    }
+                }
+            }
+        }
+
+        let appearance = appearance.or_else(|| {
+            theme_settings.background.map(|bg| if bg.relative_luminance() < 0.5 {
+                ThemeAppearance::Dark
+            } else {
+                ThemeAppearance::Light
+            })
+        });
+
+        Ok(Theme {
+            name: name,
+            author: author,
+            appearance: appearance,
+            settings: theme_settings,
+            scopes: scopes,
+        })
+    }
+
+    /// The inverse of `parse_settings`: rebuilds the plist `Settings` this
+    /// theme would parse back into, so it can be round-tripped out to a
+    /// `.tmTheme` file with `write_to`.
+    pub fn to_settings(&self) -> Settings {
+        let mut root = BTreeMap::new();
+        if let Some(ref name) = self.name {
+            root.insert("name".to_owned(), Settings::String(name.clone()));
+        }
+        if let Some(ref author) = self.author {
+            root.insert("author".to_owned(), Settings::String(author.clone()));
+        }
+        if let Some(appearance) = self.appearance {
+            let s = match appearance {
+                ThemeAppearance::Dark => "dark",
+                ThemeAppearance::Light => "light",
+            };
+            root.insert("appearance".to_owned(), Settings::String(s.to_owned()));
+        }
+
+        let mut items = Vec::new();
+        let mut global = BTreeMap::new();
+        macro_rules! put_color {
+            ($field:ident, $key:expr) => {
+                if let Some(c) = self.settings.$field {
+                    global.insert($key.to_owned(), Settings::String(format_hex_color(c)));
+                }
+            }
+        }
+        put_color!(foreground, "foreground");
+        put_color!(background, "background");
+        put_color!(caret, "caret");
+        put_color!(line_highlight, "lineHighlight");
+        put_color!(selection, "selection");
+        put_color!(selection_foreground, "selectionForeground");
+        put_color!(guide, "guide");
+        put_color!(gutter, "gutter");
+        put_color!(gutter_foreground, "gutterForeground");
+
+        let mut global_entry = BTreeMap::new();
+        global_entry.insert("settings".to_owned(), Settings::Dictionary(global));
+        items.push(Settings::Dictionary(global_entry));
+
+        for item in &self.scopes {
+            let mut entry = BTreeMap::new();
+            entry.insert("scope".to_owned(), Settings::String(item.scope.0.clone()));
+            let mut style = BTreeMap::new();
+            if let Some(c) = item.style.foreground {
+                style.insert("foreground".to_owned(), Settings::String(format_hex_color(c)));
+            }
+            if let Some(c) = item.style.background {
+                style.insert("background".to_owned(), Settings::String(format_hex_color(c)));
+            }
+            entry.insert("settings".to_owned(), Settings::Dictionary(style));
+            items.push(Settings::Dictionary(entry));
+        }
+        root.insert("settings".to_owned(), Settings::Array(items));
+
+        Settings::Dictionary(root)
+    }
+
+    /// Serializes this theme as a `.tmTheme` plist to anything
+    /// implementing `Write`, so it can be written to a file and re-opened
+    /// by Sublime, TextMate, or `ThemeSet::get_theme` itself.
+    pub fn write_to<W: Write>(&self, writer: W) -> Result<(), SettingsError> {
+        write_plist(&self.to_settings(), writer)
+    }
+}
+
+fn format_hex_color(c: Color) -> String {
+    if c.a == 0xFF {
+        format!("#{:02x}{:02x}{:02x}", c.r, c.g, c.b)
+    } else {
+        format!("#{:02x}{:02x}{:02x}{:02x}", c.r, c.g, c.b, c.a)
+    }
+}
+
+fn parse_theme_settings(settings: &Settings) -> Result<ThemeSettings, ParseThemeError> {
+    let d = try!(dict(settings));
+    Ok(ThemeSettings {
+        foreground: d.get("foreground").and_then(color),
+        background: d.get("background").and_then(color),
+        caret: d.get("caret").and_then(color),
+        line_highlight: d.get("lineHighlight").and_then(color),
+        selection: d.get("selection").and_then(color),
+        selection_foreground: d.get("selectionForeground").and_then(color),
+        guide: d.get("guide").and_then(color),
+        gutter: d.get("gutter").and_then(color),
+        gutter_foreground: d.get("gutterForeground").and_then(color),
+    })
+}
+
+fn parse_style_modifier(settings: &Settings) -> Result<StyleModifier, ParseThemeError> {
+    let d = try!(dict(settings));
+    Ok(StyleModifier {
+        foreground: d.get("foreground").and_then(color),
+        background: d.get("background").and_then(color),
+        font_style: None,
+    })
+}
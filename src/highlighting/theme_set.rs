@@ -1,13 +1,24 @@
-use super::theme::{Theme, ParseThemeError};
+use super::theme::{Theme, ThemeAppearance, ParseThemeError};
 use super::settings::*;
-use std::collections::BTreeMap;
+use dumps::{dump_to_file, from_dump_file, dump_to_reader, from_reader};
+use dirs;
+use std::collections::{BTreeMap, BTreeSet};
+use std::env;
 use std::path::{Path, PathBuf};
-use std::io::{Error as IoError, BufReader};
+use std::io::{Error as IoError, BufReader, Read, Write};
 use walkdir::WalkDir;
 use std::io;
 use std::fs::File;
 use walkdir;
 
+/// A handful of popular themes, pre-parsed and dumped once with
+/// `dump_to_file` so `load_defaults` only has to do a binary deserialize,
+/// not parse five XML plists, on every startup. Generated from the source
+/// themes in `assets/default_themes/` by the `gen_default_themes` example;
+/// re-run `cargo run --example gen_default_themes` and commit the result
+/// after editing one of those files.
+const DEFAULT_THEMES_DUMP: &'static [u8] = include_bytes!("../../assets/default_themes.themedump");
+
 #[derive(Debug, RustcEncodable, RustcDecodable)]
 pub struct ThemeSet {
     pub themes: BTreeMap<String, Theme>,
@@ -20,6 +31,11 @@ pub enum ThemeSetError {
     ParseTheme(ParseThemeError),
     ReadSettings(SettingsError),
     BadPath,
+    /// A theme's `inherits`/`parent` chain names the same theme twice.
+    /// The `String` is the theme name that repeated.
+    InheritanceCycle(String),
+    /// A theme names a parent that could not be found next to it.
+    UndefinedParent(String),
 }
 
 impl From<SettingsError> for ThemeSetError {
@@ -44,7 +60,7 @@ impl ThemeSet {
     /// Returns all the themes found in a folder, good for enumerating before loading one with get_theme
     pub fn discover_theme_paths<P: AsRef<Path>>(folder: P) -> Result<Vec<PathBuf>, ThemeSetError> {
         let mut themes = Vec::new();
-        for entry in WalkDir::new(folder) {
+        for entry in WalkDir::new(Self::expand_tilde(folder.as_ref())) {
             let entry = try!(entry.map_err(|e| ThemeSetError::WalkDir(e)));
             if entry.path().extension().map(|e| e == "tmTheme").unwrap_or(false) {
                 themes.push(entry.path().to_owned());
@@ -53,8 +69,54 @@ impl ThemeSet {
         Ok(themes)
     }
 
+    /// Gathers tmTheme files from the conventional per-user and system
+    /// theme locations - `$XDG_CONFIG_HOME/syntect/themes` (falling back
+    /// to `~/.config/syntect/themes`) and a couple of system data dirs -
+    /// merging and de-duplicating by theme name, with user locations
+    /// taking precedence over system ones on a name collision. Lets an
+    /// application embedding syntect pick up user-installed themes
+    /// without hardcoding paths.
+    pub fn discover_default_paths() -> Vec<PathBuf> {
+        let mut user_roots = Vec::new();
+        if let Ok(xdg) = env::var("XDG_CONFIG_HOME") {
+            user_roots.push(PathBuf::from(xdg).join("syntect/themes"));
+        } else if let Some(home) = dirs::home_dir() {
+            user_roots.push(home.join(".config/syntect/themes"));
+        }
+
+        let system_roots =
+            [PathBuf::from("/usr/local/share/syntect/themes"), PathBuf::from("/usr/share/syntect/themes")];
+
+        let mut seen_names = BTreeSet::new();
+        let mut paths = Vec::new();
+        for root in user_roots.iter().chain(system_roots.iter()) {
+            if let Ok(found) = Self::discover_theme_paths(root) {
+                for path in found {
+                    let name = path.file_stem().and_then(|s| s.to_str()).map(|s| s.to_owned());
+                    if let Some(name) = name {
+                        if seen_names.insert(name) {
+                            paths.push(path);
+                        }
+                    }
+                }
+            }
+        }
+        paths
+    }
+
+    /// Expands a leading `~` to the current user's home directory, so
+    /// callers can pass paths like `~/.config/syntect/themes` straight
+    /// through from user-facing config without doing the expansion
+    /// themselves.
+    fn expand_tilde(path: &Path) -> PathBuf {
+        match path.strip_prefix("~") {
+            Ok(rest) => dirs::home_dir().map(|home| home.join(rest)).unwrap_or_else(|| path.to_owned()),
+            Err(_) => path.to_owned(),
+        }
+    }
+
     fn read_file(path: &Path) -> Result<BufReader<File>, ThemeSetError> {
-        let reader = try!(File::open(path));
+        let reader = try!(File::open(Self::expand_tilde(path)));
         Ok(BufReader::new(reader))
     }
 
@@ -63,28 +125,297 @@ impl ThemeSet {
     }
 
     /// Loads a theme given a path to a .tmTheme file
+    ///
+    /// If the theme declares a parent via an `inherits` (or `parent`) key,
+    /// the parent is loaded first (as a sibling `.tmTheme` file) and the
+    /// child's settings are deep-merged onto it before parsing, so the
+    /// child only has to specify what it overrides. Parents can chain
+    /// arbitrarily deep; a repeated name anywhere in the chain is reported
+    /// as `ThemeSetError::InheritanceCycle` rather than looping forever.
     pub fn get_theme<P: AsRef<Path>>(path: P) -> Result<Theme, ThemeSetError> {
-        Ok(try!(Theme::parse_settings(try!(Self::read_plist(path.as_ref())))))
+        let path = Self::expand_tilde(path.as_ref());
+        let settings = try!(Self::read_plist(&path));
+        let merged = try!(Self::resolve_inheritance(&path, settings, &mut BTreeSet::new()));
+        Ok(try!(Theme::parse_settings(merged)))
+    }
+
+    /// Resolves `inherits`/`parent` by recursively merging the named
+    /// parent theme's settings underneath `settings`, using `path`'s
+    /// directory to locate the parent (as `<parent>.tmTheme`).
+    fn resolve_inheritance(path: &Path,
+                            settings: Settings,
+                            stack: &mut BTreeSet<String>)
+                            -> Result<Settings, ThemeSetError> {
+        let parent_name = Self::parent_name(&settings);
+        let parent_name = match parent_name {
+            Some(name) => name,
+            None => return Ok(settings),
+        };
+
+        if !stack.insert(parent_name.clone()) {
+            return Err(ThemeSetError::InheritanceCycle(parent_name));
+        }
+
+        let parent_path = path.with_file_name(format!("{}.tmTheme", parent_name));
+        if !parent_path.is_file() {
+            return Err(ThemeSetError::UndefinedParent(parent_name));
+        }
+        let parent_settings = try!(Self::read_plist(&parent_path));
+        let parent_settings = try!(Self::resolve_inheritance(&parent_path, parent_settings, stack));
+        stack.remove(&parent_name);
+
+        Ok(Self::merge_settings(parent_settings, settings))
+    }
+
+    fn parent_name(settings: &Settings) -> Option<String> {
+        let dict = match *settings {
+            Settings::Dictionary(ref d) => d,
+            _ => return None,
+        };
+        dict.get("inherits")
+            .or_else(|| dict.get("parent"))
+            .and_then(|v| v.as_string())
+            .map(|s| s.to_owned())
+    }
+
+    /// Deep-merges `child` onto `parent`: any top-level key the child sets
+    /// (other than `inherits`/`parent`, which are already resolved) wins
+    /// outright, except `settings`, whose value is the single array mixing
+    /// the theme's one global-color entry (the item with no `scope` key)
+    /// and all of its per-scope entries. That array is merged entry by
+    /// entry: a child entry either replaces the parent entry with the
+    /// same `scope` selector (or the global entry, when neither has one)
+    /// or is appended.
+    fn merge_settings(parent: Settings, child: Settings) -> Settings {
+        let (mut parent_dict, child_dict) = match (parent, child) {
+            (Settings::Dictionary(p), Settings::Dictionary(c)) => (p, c),
+            (_, child) => return child,
+        };
+
+        for (key, child_value) in child_dict {
+            if key == "settings" {
+                let merged = match parent_dict.remove(&key) {
+                    Some(parent_value) => Self::merge_theme_items(parent_value, child_value),
+                    None => child_value,
+                };
+                parent_dict.insert(key, merged);
+            } else if key == "inherits" || key == "parent" {
+                // Already resolved; don't propagate it into the merged result.
+            } else {
+                parent_dict.insert(key, child_value);
+            }
+        }
+        Settings::Dictionary(parent_dict)
+    }
+
+    /// Merges the `settings` array itself (see `merge_settings`).
+    fn merge_theme_items(parent_items: Settings, child_items: Settings) -> Settings {
+        let mut items = match parent_items {
+            Settings::Array(items) => items,
+            _ => Vec::new(),
+        };
+        let child_items = match child_items {
+            Settings::Array(items) => items,
+            other => return other,
+        };
+
+        for child_item in child_items {
+            let child_selector = Self::scope_selector(&child_item);
+            let existing = items.iter().position(|item| Self::scope_selector(item) == child_selector);
+            match existing {
+                Some(index) if child_selector.is_none() => {
+                    let merged = Self::merge_global_item(items.remove(index), child_item);
+                    items.insert(index, merged);
+                }
+                Some(index) => items[index] = child_item,
+                None => items.push(child_item),
+            }
+        }
+        Settings::Array(items)
+    }
+
+    /// Merges the lone global-color entry (no `scope` key): the child's
+    /// inner `settings` dict wins key-by-key, but any parent-only key
+    /// (e.g. `background`, when the child only sets `foreground`) survives
+    /// rather than being dropped along with the rest of the parent's dict.
+    fn merge_global_item(parent_item: Settings, child_item: Settings) -> Settings {
+        let mut parent_dict = match parent_item {
+            Settings::Dictionary(d) => d,
+            _ => return child_item,
+        };
+        let child_dict = match child_item {
+            Settings::Dictionary(d) => d,
+            other => return other,
+        };
+
+        let parent_settings = parent_dict.remove("settings");
+        let child_settings = child_dict.get("settings").cloned();
+
+        let mut merged_dict = child_dict;
+        let merged_settings = match (parent_settings, child_settings) {
+            (Some(Settings::Dictionary(mut p)), Some(Settings::Dictionary(c))) => {
+                for (key, value) in c {
+                    p.insert(key, value);
+                }
+                Some(Settings::Dictionary(p))
+            }
+            (parent_settings, None) => parent_settings,
+            (_, child_settings) => child_settings,
+        };
+        match merged_settings {
+            Some(settings) => {
+                merged_dict.insert("settings".to_owned(), settings);
+            }
+            None => {
+                merged_dict.remove("settings");
+            }
+        }
+        Settings::Dictionary(merged_dict)
+    }
+
+    /// The `scope` key of a `settings` array entry, or `None` both for the
+    /// lone global-color entry and for anything malformed.
+    fn scope_selector(item: &Settings) -> Option<String> {
+        match *item {
+            Settings::Dictionary(ref d) => d.get("scope").and_then(|v| v.as_string()).map(|s| s.to_owned()),
+            _ => None,
+        }
     }
 
     /// Loads all the themes in a folder
     pub fn load_from_folder<P: AsRef<Path>>(folder: P) -> Result<ThemeSet, ThemeSetError> {
-        let paths = try!(Self::discover_theme_paths(folder));
         let mut map = BTreeMap::new();
-        for p in paths.iter() {
-            let theme = try!(Self::get_theme(p));
-            let basename =
-                try!(p.file_stem().and_then(|x| x.to_str()).ok_or(ThemeSetError::BadPath));
-            map.insert(basename.to_owned(), theme);
+        for entry in WalkDir::new(Self::expand_tilde(folder.as_ref())) {
+            let entry = try!(entry.map_err(|e| ThemeSetError::WalkDir(e)));
+            let path = entry.path();
+            if path.extension().map(|e| e == "tmTheme").unwrap_or(false) {
+                let theme = try!(Self::get_theme(path));
+                let basename =
+                    try!(path.file_stem().and_then(|x| x.to_str()).ok_or(ThemeSetError::BadPath));
+                map.insert(basename.to_owned(), theme);
+            } else if path.extension().map(|e| e == "tmThemeFamily").unwrap_or(false) {
+                for (name, theme) in try!(Self::load_family(path)) {
+                    map.insert(name, theme);
+                }
+            }
         }
         Ok(ThemeSet { themes: map })
     }
+
+    /// Loads a `.tmThemeFamily` file: a single file declaring a `name`,
+    /// optional `author`, and a `themes` dictionary mapping each theme's
+    /// name to its settings, inline. Lets a family of closely related
+    /// themes (e.g. a dark and light variant of the same palette) ship as
+    /// one file instead of one `.tmTheme` per variant.
+    fn load_family(path: &Path) -> Result<Vec<(String, Theme)>, ThemeSetError> {
+        let settings = try!(Self::read_plist(path));
+        let mut root = match settings {
+            Settings::Dictionary(d) => d,
+            _ => return Err(ThemeSetError::BadPath),
+        };
+        let family_author = root.remove("author").and_then(|v| v.as_string().map(|s| s.to_owned()));
+        let themes = match root.remove("themes") {
+            Some(Settings::Dictionary(d)) => d,
+            _ => return Ok(Vec::new()),
+        };
+
+        let mut result = Vec::new();
+        for (name, theme_settings) in themes {
+            let mut theme = try!(Theme::parse_settings(theme_settings));
+            if theme.author.is_none() {
+                theme.author = family_author.clone();
+            }
+            result.push((name, theme));
+        }
+        Ok(result)
+    }
+
+    /// The names of the loaded themes that are `Dark` or `Light`
+    /// (whichever `appearance` is asked for), so a host application can
+    /// offer a picker or auto-select based on OS appearance.
+    pub fn themes_by_appearance(&self, appearance: ThemeAppearance) -> Vec<&str> {
+        self.themes
+            .iter()
+            .filter(|&(_, theme)| theme.appearance == Some(appearance))
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// The name stems (file names without the `.tmTheme` extension) of
+    /// every theme found in `folder`, handy for presenting a picker
+    /// before calling `load`.
+    pub fn list_names<P: AsRef<Path>>(folder: P) -> Result<Vec<String>, ThemeSetError> {
+        let paths = try!(Self::discover_theme_paths(folder));
+        paths.iter()
+            .map(|p| {
+                p.file_stem()
+                    .and_then(|x| x.to_str())
+                    .map(|s| s.to_owned())
+                    .ok_or(ThemeSetError::BadPath)
+            })
+            .collect()
+    }
+
+    /// Loads the theme named `name` (its `.tmTheme` file stem), looking
+    /// first in `user_folder` so a user's own themes - or overrides of
+    /// bundled ones - always win, then falling back to `default_folder`.
+    pub fn load<P: AsRef<Path>>(name: &str,
+                                 user_folder: P,
+                                 default_folder: P)
+                                 -> Result<Theme, ThemeSetError> {
+        let user_path = Self::expand_tilde(user_folder.as_ref()).join(format!("{}.tmTheme", name));
+        if user_path.is_file() {
+            return Self::get_theme(user_path);
+        }
+        let default_path = Self::expand_tilde(default_folder.as_ref()).join(format!("{}.tmTheme", name));
+        Self::get_theme(default_path)
+    }
+
+    /// A `ThemeSet` built entirely from themes bundled into this binary,
+    /// so a reasonable default is always available with no filesystem
+    /// access - useful as a starting point before layering a user's own
+    /// themes on top with `load_from_folder`.
+    ///
+    /// The bundle is a single pre-parsed binary dump produced ahead of
+    /// time with `dump_to_file` (see `assets/default_themes.themedump`)
+    /// and loaded with `from_reader`, rather than parsing several XML
+    /// plists at every startup - the exact cost `dump_to_file`/
+    /// `from_dump_file` exist to avoid.
+    pub fn load_defaults() -> ThemeSet {
+        Self::from_reader(DEFAULT_THEMES_DUMP).expect("bundled default themes dump is always valid")
+    }
+
+    /// Serializes this `ThemeSet` to `path` in a compact binary format, so
+    /// it can be reloaded with `from_dump_file` without re-parsing any
+    /// plists.
+    pub fn dump_to_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        dump_to_file(self, Self::expand_tilde(path.as_ref()))
+    }
+
+    /// Loads a `ThemeSet` previously saved with `dump_to_file`.
+    pub fn from_dump_file<P: AsRef<Path>>(path: P) -> io::Result<ThemeSet> {
+        from_dump_file(Self::expand_tilde(path.as_ref()))
+    }
+
+    /// Serializes this `ThemeSet` to anything implementing `Write`.
+    pub fn dump_to_reader<W: Write>(&self, writer: W) -> io::Result<()> {
+        dump_to_reader(self, writer)
+    }
+
+    /// Loads a `ThemeSet` previously saved with `dump_to_reader`.
+    pub fn from_reader<R: Read>(reader: R) -> io::Result<ThemeSet> {
+        from_reader(reader)
+    }
 }
 
 
 #[cfg(test)]
 mod tests {
-    use highlighting::{ThemeSet, Color};
+    use highlighting::{ThemeSet, Theme, ThemeAppearance, Color};
+    use highlighting::settings::read_plist;
+    use std::env;
+    use std::fs::{self, File};
+    use std::io::Write;
     #[test]
     fn can_parse_common_themes() {
         let themes = ThemeSet::load_from_folder("testdata").unwrap();
@@ -109,4 +440,227 @@ mod tests {
                    });
         // assert!(false);
     }
+
+    #[test]
+    fn can_round_trip_a_theme() {
+        let theme = ThemeSet::get_theme("testdata/spacegray/base16-ocean.dark.tmTheme").unwrap();
+
+        let mut dumped = Vec::new();
+        theme.write_to(&mut dumped).unwrap();
+
+        let settings = read_plist(&dumped[..]).unwrap();
+        let round_tripped = Theme::parse_settings(settings).unwrap();
+
+        assert_eq!(round_tripped.settings.selection, theme.settings.selection);
+        assert_eq!(round_tripped.scopes[0].style.foreground, theme.scopes[0].style.foreground);
+        assert_eq!(round_tripped.appearance, theme.appearance);
+    }
+
+    const PARENT_THEME: &'static str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>name</key>
+    <string>Parent</string>
+    <key>settings</key>
+    <array>
+        <dict>
+            <key>settings</key>
+            <dict>
+                <key>background</key>
+                <string>#000000</string>
+                <key>foreground</key>
+                <string>#ffffff</string>
+            </dict>
+        </dict>
+        <dict>
+            <key>scope</key>
+            <string>comment</string>
+            <key>settings</key>
+            <dict>
+                <key>foreground</key>
+                <string>#888888</string>
+            </dict>
+        </dict>
+    </array>
+</dict>
+</plist>
+"#;
+
+    const CHILD_THEME: &'static str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>name</key>
+    <string>Child</string>
+    <key>parent</key>
+    <string>parent</string>
+    <key>settings</key>
+    <array>
+        <dict>
+            <key>settings</key>
+            <dict>
+                <key>foreground</key>
+                <string>#eeeeee</string>
+            </dict>
+        </dict>
+        <dict>
+            <key>scope</key>
+            <string>comment</string>
+            <key>settings</key>
+            <dict>
+                <key>foreground</key>
+                <string>#555555</string>
+            </dict>
+        </dict>
+        <dict>
+            <key>scope</key>
+            <string>string</string>
+            <key>settings</key>
+            <dict>
+                <key>foreground</key>
+                <string>#00ff00</string>
+            </dict>
+        </dict>
+    </array>
+</dict>
+</plist>
+"#;
+
+    #[test]
+    fn inherited_theme_overrides_and_preserves_parent() {
+        let dir = env::temp_dir().join("syntect-theme-inheritance-test");
+        fs::create_dir_all(&dir).unwrap();
+
+        let parent_path = dir.join("parent.tmTheme");
+        File::create(&parent_path).unwrap().write_all(PARENT_THEME.as_bytes()).unwrap();
+        let child_path = dir.join("child.tmTheme");
+        File::create(&child_path).unwrap().write_all(CHILD_THEME.as_bytes()).unwrap();
+
+        let theme = ThemeSet::get_theme(&child_path).unwrap();
+
+        // Inherited untouched from the parent.
+        assert_eq!(theme.settings.background.unwrap(), Color { r: 0, g: 0, b: 0, a: 0xff });
+        // Overridden by the child.
+        assert_eq!(theme.settings.foreground.unwrap(), Color { r: 0xee, g: 0xee, b: 0xee, a: 0xff });
+
+        assert_eq!(theme.scopes.len(), 2);
+        let comment = theme.scopes.iter().find(|i| i.scope.0 == "comment").unwrap();
+        assert_eq!(comment.style.foreground.unwrap(), Color { r: 0x55, g: 0x55, b: 0x55, a: 0xff });
+        let string_scope = theme.scopes.iter().find(|i| i.scope.0 == "string").unwrap();
+        assert_eq!(string_scope.style.foreground.unwrap(), Color { r: 0, g: 0xff, b: 0, a: 0xff });
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn theme_set_round_trips_through_binary_cache() {
+        let theme = ThemeSet::get_theme("testdata/spacegray/base16-ocean.dark.tmTheme").unwrap();
+        let mut themes = BTreeMap::new();
+        themes.insert("ocean".to_owned(), theme);
+        let set = ThemeSet { themes: themes };
+
+        let mut dumped = Vec::new();
+        set.dump_to_reader(&mut dumped).unwrap();
+        let loaded = ThemeSet::from_reader(&dumped[..]).unwrap();
+        assert_eq!(loaded.themes, set.themes);
+    }
+
+    #[test]
+    fn load_prefers_user_folder_over_default_folder() {
+        let dir = env::temp_dir().join("syntect-theme-load-test");
+        let user_dir = dir.join("user");
+        let default_dir = dir.join("default");
+        fs::create_dir_all(&user_dir).unwrap();
+        fs::create_dir_all(&default_dir).unwrap();
+
+        File::create(default_dir.join("my-theme.tmTheme")).unwrap().write_all(PARENT_THEME.as_bytes()).unwrap();
+        let mut user_theme = PARENT_THEME.replace("Parent", "User Override");
+        user_theme = user_theme.replace("#000000", "#123456");
+        File::create(user_dir.join("my-theme.tmTheme")).unwrap().write_all(user_theme.as_bytes()).unwrap();
+
+        let theme = ThemeSet::load("my-theme", &user_dir, &default_dir).unwrap();
+        assert_eq!(theme.name.unwrap(), "User Override");
+        assert_eq!(theme.settings.background.unwrap(), Color { r: 0x12, g: 0x34, b: 0x56, a: 0xff });
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    const THEME_FAMILY: &'static str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>name</key>
+    <string>My Family</string>
+    <key>author</key>
+    <string>Family Author</string>
+    <key>themes</key>
+    <dict>
+        <key>My Dark</key>
+        <dict>
+            <key>settings</key>
+            <array>
+                <dict>
+                    <key>settings</key>
+                    <dict>
+                        <key>background</key>
+                        <string>#000000</string>
+                    </dict>
+                </dict>
+            </array>
+        </dict>
+        <key>My Light</key>
+        <dict>
+            <key>settings</key>
+            <array>
+                <dict>
+                    <key>settings</key>
+                    <dict>
+                        <key>background</key>
+                        <string>#ffffff</string>
+                    </dict>
+                </dict>
+            </array>
+        </dict>
+    </dict>
+</dict>
+</plist>
+"#;
+
+    #[test]
+    fn loads_theme_family_file_with_inferred_appearance() {
+        let dir = env::temp_dir().join("syntect-theme-family-test");
+        fs::create_dir_all(&dir).unwrap();
+        File::create(dir.join("family.tmThemeFamily")).unwrap().write_all(THEME_FAMILY.as_bytes()).unwrap();
+
+        let set = ThemeSet::load_from_folder(&dir).unwrap();
+
+        let dark = &set.themes["My Dark"];
+        assert_eq!(dark.author.as_ref().unwrap(), "Family Author");
+        assert_eq!(dark.appearance, Some(ThemeAppearance::Dark));
+
+        let light = &set.themes["My Light"];
+        assert_eq!(light.author.as_ref().unwrap(), "Family Author");
+        assert_eq!(light.appearance, Some(ThemeAppearance::Light));
+
+        assert_eq!(set.themes_by_appearance(ThemeAppearance::Dark), vec!["My Dark"]);
+        assert_eq!(set.themes_by_appearance(ThemeAppearance::Light), vec!["My Light"]);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn tilde_path_inheritance_resolves_parent() {
+        let home = env::temp_dir().join("syntect-tilde-home-test");
+        fs::create_dir_all(&home).unwrap();
+        env::set_var("HOME", &home);
+
+        File::create(home.join("parent.tmTheme")).unwrap().write_all(PARENT_THEME.as_bytes()).unwrap();
+        File::create(home.join("child.tmTheme")).unwrap().write_all(CHILD_THEME.as_bytes()).unwrap();
+
+        let theme = ThemeSet::get_theme("~/child.tmTheme").unwrap();
+        assert_eq!(theme.settings.background.unwrap(), Color { r: 0, g: 0, b: 0, a: 0xff });
+
+        fs::remove_dir_all(&home).ok();
+    }
 }
\ No newline at end of file